@@ -0,0 +1,26 @@
+use crate::BixResult;
+
+mod yaz0;
+
+/// Container formats `bix` can transparently decompress before rendering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    Yaz0,
+}
+
+impl Codec {
+    /// Inspects the leading bytes of `data` for a known container magic.
+    pub fn sniff(data: &[u8]) -> Option<Self> {
+        if data.starts_with(yaz0::MAGIC) {
+            Some(Codec::Yaz0)
+        } else {
+            None
+        }
+    }
+
+    pub fn decompress(self, data: &[u8]) -> BixResult<Vec<u8>> {
+        match self {
+            Codec::Yaz0 => yaz0::decompress(data),
+        }
+    }
+}
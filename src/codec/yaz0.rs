@@ -0,0 +1,110 @@
+use crate::BixResult;
+
+/// ASCII magic at the start of every Yaz0 stream.
+pub const MAGIC: &[u8] = b"Yaz0";
+
+/// Upper bound on a stream's claimed decompressed size, so a corrupt or malicious header
+/// can't make us reserve gigabytes of memory before the first read error surfaces.
+const MAX_DECOMPRESSED_SIZE: usize = 512 * 1024 * 1024;
+
+/// Decodes a Yaz0-compressed buffer. The header is the magic, a big-endian u32
+/// decompressed size, then 8 reserved bytes. The body is a sequence of one-byte group
+/// headers consumed MSB-first: a `1` bit copies one literal source byte, a `0` bit reads
+/// `b1 b2` and forms a back-reference with `distance = (((b1 & 0x0F) << 8) | b2) + 1` and
+/// `count` taken from `b1`'s high nibble (0 means read one more byte and add 0x12,
+/// otherwise add 2), then copies `count` bytes one at a time so overlapping runs repeat.
+pub fn decompress(data: &[u8]) -> BixResult<Vec<u8>> {
+    if data.len() < 16 || &data[0..4] != MAGIC {
+        return Err("not a Yaz0 stream".into());
+    }
+
+    let decompressed_size = u32::from_be_bytes(data[4..8].try_into()?) as usize;
+    if decompressed_size > MAX_DECOMPRESSED_SIZE {
+        return Err(format!(
+            "Yaz0 decompressed size {} exceeds sanity cap of {} bytes",
+            decompressed_size, MAX_DECOMPRESSED_SIZE
+        )
+        .into());
+    }
+    let mut out = Vec::with_capacity(decompressed_size);
+    let mut src = 16;
+
+    let next_byte = |src: &mut usize| -> BixResult<u8> {
+        let byte = *data.get(*src).ok_or("unexpected end of Yaz0 stream")?;
+        *src += 1;
+        Ok(byte)
+    };
+
+    while out.len() < decompressed_size {
+        let group = next_byte(&mut src)?;
+
+        for bit in (0..8).rev() {
+            if out.len() >= decompressed_size {
+                break;
+            }
+
+            if (group >> bit) & 1 == 1 {
+                let byte = next_byte(&mut src)?;
+                out.push(byte);
+            } else {
+                let b1 = next_byte(&mut src)?;
+                let b2 = next_byte(&mut src)?;
+
+                let distance = (((b1 as usize & 0x0F) << 8) | b2 as usize) + 1;
+                let count = match b1 >> 4 {
+                    0 => next_byte(&mut src)? as usize + 0x12,
+                    n => n as usize + 2,
+                };
+
+                let start = out.len().checked_sub(distance).ok_or("Yaz0 back-reference before start of output")?;
+                for pos in start..start + count {
+                    out.push(out[pos]);
+                }
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(decompressed_size: u32, body: &[u8]) -> Vec<u8> {
+        let mut data = Vec::new();
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&decompressed_size.to_be_bytes());
+        data.extend_from_slice(&[0u8; 8]);
+        data.extend_from_slice(body);
+        data
+    }
+
+    #[test]
+    fn decompresses_all_literal_bytes() {
+        // group 0xF0: the top 4 bits are literal copies, enough to cover 4 output bytes.
+        let data = stream(4, &[0xF0, b'a', b'b', b'c', b'd']);
+        assert_eq!(decompress(&data).unwrap(), b"abcd");
+    }
+
+    #[test]
+    fn decompresses_an_overlapping_back_reference() {
+        // group 0x80: one literal 'A', then a back-reference with distance 1, count 3,
+        // which repeats the just-written byte to produce "AAAA".
+        let data = stream(4, &[0x80, b'A', 0x10, 0x00]);
+        assert_eq!(decompress(&data).unwrap(), b"AAAA");
+    }
+
+    #[test]
+    fn rejects_a_claimed_size_over_the_sanity_cap() {
+        let data = stream(u32::MAX, &[]);
+        assert!(decompress(&data).is_err());
+    }
+
+    #[test]
+    fn rejects_input_missing_the_magic() {
+        let mut data = stream(4, &[0xF0, 1, 2, 3, 4]);
+        data[0] = b'X';
+        assert!(decompress(&data).is_err());
+    }
+}
@@ -0,0 +1,5 @@
+mod crc32;
+mod sha1;
+
+pub use crc32::crc32;
+pub use sha1::sha1;
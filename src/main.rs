@@ -1,7 +1,10 @@
-use std::{fs::{File, OpenOptions}, io::{Read, Seek, Write}, path::PathBuf};
-use clap::{Parser, Subcommand};
+use std::{fs::{File, OpenOptions}, io::{BufReader, Cursor, IsTerminal, Read, Seek, Write}, path::{Path, PathBuf}};
+use clap::{Parser, Subcommand, ValueEnum};
 
-type BixResult<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+mod codec;
+mod digest;
+
+pub(crate) type BixResult<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
 #[derive(Parser, Debug)]
 #[command(about, long_about = None, version)]
@@ -15,9 +18,10 @@ pub struct Args {
 pub enum ArgCommand {
     /// Prints out hexadecimal representation of a specified file
     View {
-        file: PathBuf,
+        /// File to read, `-` or omitted reads from stdin
+        file: Option<PathBuf>,
 
-        /// Hexadecimal offset in the file, e.g 0x10 
+        /// Hexadecimal offset in the file, e.g 0x10
         #[arg(short, long, default_value = "0x0", value_parser = parse_offset)]
         offset: u64,
 
@@ -45,25 +49,493 @@ pub enum ArgCommand {
         /// Same as using `--no-addr --no-group --no-ascii` together
         #[arg(long)]
         raw: bool,
+
+        /// Numeric format used to render each byte
+        #[arg(short, long, value_enum, default_value_t = Format::Hex)]
+        format: Format,
+
+        /// Colorize each byte cell based on its value (auto-disabled when not a TTY or NO_COLOR is set)
+        #[arg(long)]
+        color: bool,
+
+        /// Emit the selected bytes as a source-code array literal instead of a hexdump
+        #[arg(long, value_enum)]
+        array: Option<ArrayFormat>,
+
+        /// Wrap the `--array` output in a named declaration, e.g `--func FIRMWARE`
+        #[arg(long, requires = "array")]
+        func: Option<String>,
+
+        /// Skip codec detection and dump the bytes exactly as read, even if a known
+        /// compression magic (e.g Yaz0) is present
+        #[arg(long)]
+        raw_bytes: bool,
     },
     /// Writes N bytes at the specified offset into a specified file
     Set {
         file: PathBuf,
-        
+
         /// Array of bytes in hex format to insert. E.g `bix set filename AA DD CC BA`
-        #[arg(required = true, value_parser = parse_byte)]
+        #[arg(value_parser = parse_byte, required_unless_present = "delete", conflicts_with = "delete")]
         bytes: Vec<u8>,
 
         /// Hexadecimal offset in the file, e.g 0x10
         #[arg(short, long, default_value = "0x0", value_parser = parse_offset)]
         offset: u64,
+
+        /// Shift the tail of the file forward and insert `bytes` at `offset`, growing the file
+        #[arg(long, conflicts_with = "delete")]
+        insert: bool,
+
+        /// Remove N bytes at `offset`, shifting the tail back and shrinking the file
+        #[arg(long, conflicts_with = "insert")]
+        delete: Option<usize>,
+    },
+    /// Reconstructs raw bytes from a `bix view` dump, the inverse of `View`
+    Patch {
+        /// Dump file to parse, `-` reads from stdin
+        dump: PathBuf,
+
+        /// File to write the reconstructed bytes into
+        output: PathBuf,
+
+        /// Seek to each line's leading address instead of writing sequentially from the start,
+        /// so a dump edited in a text editor can be applied back onto the original file
+        #[arg(long)]
+        seek: bool,
+    },
+    /// Scans a file for a hex byte pattern and prints every matching offset
+    Find {
+        file: PathBuf,
+
+        /// Hex byte pattern to search for, `??` matches any byte. E.g `bix find filename AA ?? CC`
+        #[arg(required = true, value_parser = parse_pattern_byte)]
+        pattern: Vec<PatternByte>,
+
+        /// Number of bytes of surrounding context to render around each match
+        #[arg(short, long)]
+        context: Option<usize>,
+
+        /// Numeric format used to render the context window
+        #[arg(short, long, value_enum, default_value_t = Format::Hex)]
+        format: Format,
+
+        /// Colorize the context window (auto-disabled when not a TTY or NO_COLOR is set)
+        #[arg(long)]
+        color: bool,
+
+        /// Skip codec detection and search the bytes exactly as read, even if a known
+        /// compression magic (e.g Yaz0) is present
+        #[arg(long)]
+        raw_bytes: bool,
+    },
+    /// Computes an integrity digest over a byte range of a file
+    Sum {
+        /// File to read, `-` or omitted reads from stdin
+        file: Option<PathBuf>,
+
+        /// Hexadecimal offset in the file, e.g 0x10
+        #[arg(short, long, default_value = "0x0", value_parser = parse_offset)]
+        offset: u64,
+
+        /// Number of bytes to include since `offset`
+        #[arg(short, long)]
+        number: Option<usize>,
+
+        /// Digest algorithm
+        #[arg(short, long, value_enum, default_value_t = Algo::Crc32)]
+        algo: Algo,
+    }
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Lowercase hexadecimal, e.g `ab`
+    Hex,
+    /// Uppercase hexadecimal, e.g `AB`
+    #[value(name = "HEX")]
+    HexUpper,
+    /// Octal, e.g `253`
+    Octal,
+    /// Binary, e.g `10101011`
+    Binary,
+    /// Decimal, e.g `171`
+    Decimal,
+}
+
+impl Format {
+    /// Width in characters of a single rendered byte cell, used to keep the ASCII panel aligned
+    fn cell_width(self) -> usize {
+        match self {
+            Format::Hex | Format::HexUpper => 2,
+            Format::Octal | Format::Decimal => 3,
+            Format::Binary => 8,
+        }
+    }
+
+    fn render(self, byte: u8) -> String {
+        match self {
+            Format::Hex => format!("{:02x}", byte),
+            Format::HexUpper => format!("{:02X}", byte),
+            Format::Octal => format!("{:03o}", byte),
+            Format::Binary => format!("{:08b}", byte),
+            Format::Decimal => format!("{:3}", byte),
+        }
+    }
+}
+
+/// Picks a deterministic 256-color ANSI foreground code for a byte, with distinct
+/// sentinel colors for the all-zero and all-one bytes since they dominate most dumps.
+fn ansi_color_for_byte(byte: u8) -> u8 {
+    match byte {
+        0x00 => 196, // red
+        0xFF => 46,  // green
+        _ => 17 + (byte as u16 * 214 / 255) as u8,
+    }
+}
+
+fn colorize(s: &str, byte: u8) -> String {
+    format!("\x1b[38;5;{}m{}\x1b[0m", ansi_color_for_byte(byte), s)
+}
+
+fn should_colorize(requested: bool) -> bool {
+    requested && std::io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Shared knobs for rendering a byte slice as a grouped hexdump, used by both `View` and `Find`.
+struct DumpOpts {
+    width: usize,
+    no_group: bool,
+    no_addr: bool,
+    no_ascii: bool,
+    format: Format,
+    color: bool,
+}
+
+fn render_dump(buf: &[u8], offset: u64, opts: &DumpOpts) -> String {
+    let colorize_output = should_colorize(opts.color);
+    let cell = opts.format.cell_width();
+    let mut out = String::new();
+
+    for (i, chunk) in buf.chunks(opts.width).enumerate() {
+        let addr = offset + (i * opts.width) as u64;
+
+        if !opts.no_addr {
+            out.push_str(&format!("{:08X}: ", addr));
+        }
+
+        let mid = opts.width / 2;
+        for (j, byte) in chunk.iter().enumerate() {
+            let rendered = opts.format.render(*byte);
+            if colorize_output {
+                out.push_str(&colorize(&rendered, *byte));
+            } else {
+                out.push_str(&rendered);
+            }
+            out.push(' ');
+            if !opts.no_group && j + 1 == mid {
+                out.push(' ');
+            }
+        }
+
+        if !opts.no_ascii {
+            // `hex_width` is the column the `|` lands on for a *full* row, so every row pads out
+            // to it regardless of how many bytes this particular chunk actually has.
+            let hex_width = if !opts.no_group { opts.width * (cell + 1) + 1 } else { opts.width * (cell + 1) };
+            let printed_hex = chunk.len() * (cell + 1) + if !opts.no_group && mid > 0 && chunk.len() >= mid { 1 } else { 0 };
+            out.push_str(&format!("{:width$}|", "", width = hex_width - printed_hex));
+
+            for byte in chunk {
+                let ch = if (byte.is_ascii_graphic() || *byte == b' ') && !byte.is_ascii_control() {
+                    *byte as char
+                } else {
+                    '.'
+                };
+                out.push(ch);
+            }
+            out.push('|');
+        }
+
+        out.push('\n');
+    }
+
+    out
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayFormat {
+    /// `[0xAA, 0xBB, ...]`
+    Rust,
+    /// `{ 0xAA, 0xBB, ... }`
+    C,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algo {
+    #[value(name = "crc32")]
+    Crc32,
+    #[value(name = "sha1")]
+    Sha1,
+}
+
+/// Renders `buf` as a source-code array literal, optionally wrapped in a named declaration.
+fn render_array(buf: &[u8], fmt: ArrayFormat, func: Option<&str>, width: usize) -> String {
+    let (open, close) = match fmt {
+        ArrayFormat::Rust => ('[', ']'),
+        ArrayFormat::C => ('{', '}'),
+    };
+
+    let mut out = String::new();
+    match (fmt, func) {
+        (ArrayFormat::Rust, Some(name)) => out.push_str(&format!("const {}: [u8; {}] = {}\n", name, buf.len(), open)),
+        (ArrayFormat::C, Some(name)) => out.push_str(&format!("unsigned char {}[{}] = {}\n", name, buf.len(), open)),
+        (_, None) => out.push_str(&format!("{}\n", open)),
     }
+
+    for chunk in buf.chunks(width) {
+        out.push_str("    ");
+        let cells: Vec<String> = chunk.iter().map(|b| format!("0x{:02X}", b)).collect();
+        out.push_str(&cells.join(", "));
+        out.push_str(",\n");
+    }
+
+    out.push(close);
+    if func.is_some() {
+        out.push(';');
+    }
+    out.push('\n');
+    out
+}
+
+/// Opens `file` for reading, falling back to stdin when it is `-` or absent.
+/// Seekable files honor `offset` directly; stdin instead discards `offset` bytes up front.
+fn open_input(file: Option<PathBuf>, offset: u64) -> BixResult<Box<dyn Read>> {
+    match file {
+        Some(path) if path != Path::new("-") => {
+            let mut f = File::open(path)?;
+            f.seek(std::io::SeekFrom::Start(offset))?;
+            Ok(Box::new(BufReader::new(f)))
+        }
+        _ => {
+            let mut stdin = BufReader::new(std::io::stdin());
+            discard_offset(&mut stdin, offset)?;
+            Ok(Box::new(stdin))
+        }
+    }
+}
+
+/// Reads and drops `offset` bytes from `r`, the non-seekable equivalent of a `seek` used for
+/// stdin, since it's the only way to honor `--offset` on a stream that can't seek backwards.
+fn discard_offset<R: Read>(r: &mut R, offset: u64) -> BixResult<()> {
+    if offset > 0 {
+        std::io::copy(&mut r.take(offset), &mut std::io::sink())?;
+    }
+    Ok(())
+}
+
+/// Reads the bytes `View` renders. A detected codec magic (e.g Yaz0) forces a full read of the
+/// input before decompressing, since the decoder needs the whole stream up front; otherwise (or
+/// with `--raw-bytes`) only the bytes actually requested are read, so `offset`/`number` stay
+/// bounded on unseekable sources like stdin and don't force buffering a multi-GB seekable file.
+fn read_view_bytes(file: Option<PathBuf>, offset: u64, number: Option<usize>, raw_bytes: bool) -> BixResult<Vec<u8>> {
+    match file {
+        Some(path) if path != Path::new("-") => {
+            let mut f = File::open(path)?;
+            let mut probe = [0u8; 16];
+            let probe_len = f.read(&mut probe)?;
+            f.seek(std::io::SeekFrom::Start(0))?;
+            let codec = if raw_bytes { None } else { codec::Codec::sniff(&probe[..probe_len]) };
+
+            if let Some(codec) = codec {
+                let mut compressed = Vec::new();
+                f.read_to_end(&mut compressed)?;
+                let decoded = codec.decompress(&compressed)?;
+                slice_for_offset(decoded, offset, number)
+            } else {
+                f.seek(std::io::SeekFrom::Start(offset))?;
+                read_bounded(&mut f, number)
+            }
+        }
+        _ => {
+            let mut stdin = BufReader::new(std::io::stdin());
+            let mut probe = [0u8; 16];
+            let probe_len = stdin.read(&mut probe)?;
+            let codec = if raw_bytes { None } else { codec::Codec::sniff(&probe[..probe_len]) };
+
+            if let Some(codec) = codec {
+                let mut compressed = probe[..probe_len].to_vec();
+                stdin.read_to_end(&mut compressed)?;
+                let decoded = codec.decompress(&compressed)?;
+                slice_for_offset(decoded, offset, number)
+            } else {
+                let mut rest = Cursor::new(probe[..probe_len].to_vec()).chain(stdin);
+                if offset > 0 {
+                    std::io::copy(&mut (&mut rest).take(offset), &mut std::io::sink())?;
+                }
+                read_bounded(&mut rest, number)
+            }
+        }
+    }
+}
+
+/// Reads exactly `number` bytes if given, otherwise reads `r` to the end.
+fn read_bounded<R: Read>(r: &mut R, number: Option<usize>) -> BixResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    if let Some(len) = number {
+        buf.resize(len, 0);
+        r.read_exact(&mut buf)?;
+    } else {
+        r.read_to_end(&mut buf)?;
+    }
+    Ok(buf)
+}
+
+/// Slices a fully-decoded buffer to `[offset, offset + number)`, or to the end if `number`
+/// is absent.
+fn slice_for_offset(decoded: Vec<u8>, offset: u64, number: Option<usize>) -> BixResult<Vec<u8>> {
+    let start = offset as usize;
+    if let Some(len) = number {
+        let end = start.checked_add(len).ok_or("offset + number overflows")?;
+        if end > decoded.len() {
+            return Err(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "not enough bytes after offset").into());
+        }
+        Ok(decoded[start..end].to_vec())
+    } else if start >= decoded.len() {
+        Ok(Vec::new())
+    } else {
+        Ok(decoded[start..].to_vec())
+    }
+}
+
+/// Extracts the leading address (if any) and the hex-pair bytes from one line of a `View`
+/// dump, tolerating the `addr: ` column, the mid-row group space and the trailing `|ascii|` panel.
+fn parse_dump_line(line: &str) -> BixResult<(Option<u64>, Vec<u8>)> {
+    let mut rest = line.trim_end();
+    let mut addr = None;
+
+    if let Some(colon) = rest.find(':') {
+        let (head, tail) = rest.split_at(colon);
+        if !head.is_empty() && head.chars().all(|c| c.is_ascii_hexdigit()) {
+            addr = Some(u64::from_str_radix(head, 16)?);
+            rest = tail[1..].trim_start();
+        }
+    }
+
+    if let Some(pipe) = rest.find('|') {
+        rest = &rest[..pipe];
+    }
+
+    let mut bytes = Vec::new();
+    for token in rest.split_whitespace() {
+        if token.len() == 2 && token.chars().all(|c| c.is_ascii_hexdigit()) {
+            bytes.push(u8::from_str_radix(token, 16)?);
+        }
+    }
+
+    Ok((addr, bytes))
 }
 
 fn parse_byte(s: &str) -> BixResult<u8> {
     Ok(u8::from_str_radix(s, 16)?)
 }
 
+#[derive(Debug, Clone, Copy)]
+pub enum PatternByte {
+    Exact(u8),
+    /// `?`/`??`, matches any byte
+    Any,
+}
+
+fn parse_pattern_byte(s: &str) -> BixResult<PatternByte> {
+    if s == "?" || s == "??" {
+        Ok(PatternByte::Any)
+    } else {
+        Ok(PatternByte::Exact(parse_byte(s)?))
+    }
+}
+
+fn pattern_matches(window: &[u8], pattern: &[PatternByte]) -> bool {
+    window.iter().zip(pattern).all(|(byte, p)| match p {
+        PatternByte::Exact(expected) => byte == expected,
+        PatternByte::Any => true,
+    })
+}
+
+/// Scans `f` for `pattern` using an overlapping sliding window, so matches spanning
+/// buffer boundaries are still found without loading the whole file into memory.
+fn find_matches<R: Read>(f: &mut R, pattern: &[PatternByte]) -> BixResult<Vec<u64>> {
+    const CHUNK: usize = 64 * 1024;
+    let pat_len = pattern.len();
+    let keep = pat_len.saturating_sub(1);
+
+    let mut offsets = Vec::new();
+    let mut window: Vec<u8> = Vec::new();
+    let mut base = 0u64;
+    let mut buf = vec![0u8; CHUNK];
+
+    loop {
+        let n = f.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        window.extend_from_slice(&buf[..n]);
+
+        let mut i = 0;
+        while i + pat_len <= window.len() {
+            if pattern_matches(&window[i..i + pat_len], pattern) {
+                offsets.push(base + i as u64);
+            }
+            i += 1;
+        }
+
+        if window.len() > keep {
+            let drop = window.len() - keep;
+            window.drain(0..drop);
+            base += drop as u64;
+        }
+    }
+
+    Ok(offsets)
+}
+
+const SHIFT_WINDOW: usize = 64 * 1024;
+
+/// Shifts bytes in `[offset, file_len)` forward by `shift` bytes using a bounded streaming
+/// copy (read a window, write it at the shifted position, iterate from the tail backwards)
+/// so arbitrarily large files can be grown without buffering them whole.
+fn shift_forward<F: Read + Write + Seek>(f: &mut F, offset: u64, file_len: u64, shift: u64) -> BixResult<()> {
+    let mut pos = file_len;
+    while pos > offset {
+        let chunk = std::cmp::min(SHIFT_WINDOW as u64, pos - offset) as usize;
+        let read_start = pos - chunk as u64;
+        let mut window = vec![0u8; chunk];
+        f.seek(std::io::SeekFrom::Start(read_start))?;
+        f.read_exact(&mut window)?;
+        f.seek(std::io::SeekFrom::Start(read_start + shift))?;
+        f.write_all(&window)?;
+        pos = read_start;
+    }
+    Ok(())
+}
+
+/// Shifts bytes in `[offset + shift, file_len)` back by `shift` bytes, streaming forward
+/// through the tail. Returns the new end-of-data position, which the caller truncates to.
+fn shift_backward<F: Read + Write + Seek>(f: &mut F, offset: u64, file_len: u64, shift: u64) -> BixResult<u64> {
+    let mut read_pos = offset + shift;
+    let mut write_pos = offset;
+    while read_pos < file_len {
+        let chunk = std::cmp::min(SHIFT_WINDOW as u64, file_len - read_pos) as usize;
+        let mut window = vec![0u8; chunk];
+        f.seek(std::io::SeekFrom::Start(read_pos))?;
+        f.read_exact(&mut window)?;
+        f.seek(std::io::SeekFrom::Start(write_pos))?;
+        f.write_all(&window)?;
+        read_pos += chunk as u64;
+        write_pos += chunk as u64;
+    }
+    Ok(write_pos)
+}
+
 fn parse_offset(s: &str) -> BixResult<u64> {
     if let Some(stripped) = s.strip_prefix("0x") {
         Ok(u64::from_str_radix(stripped, 16)?)
@@ -75,71 +547,293 @@ fn parse_offset(s: &str) -> BixResult<u64> {
 fn main() -> BixResult<()> {
     let cli = Args::parse();
     match cli.command {
-        ArgCommand::View { file, offset, number, width, no_group, no_addr, no_ascii, raw } => {
-            let mut f = File::open(file)?;
-            f.seek(std::io::SeekFrom::Start(offset))?;
+        ArgCommand::View { file, offset, number, width, no_group, no_addr, no_ascii, raw, format, color, array, func, raw_bytes } => {
+            let buf = read_view_bytes(file, offset, number, raw_bytes)?;
 
-            let mut buf = Vec::new();
-            if let Some(len) = number {
-                buf.resize(len, 0);
-                f.read_exact(&mut buf)?;
-            } else {
-                f.read_to_end(&mut buf)?;
+            if let Some(array) = array {
+                print!("{}", render_array(&buf, array, func.as_deref(), width));
+                return Ok(());
             }
 
+            let colorize_output = should_colorize(color);
+
             if raw {
                 for byte in &buf {
-                    print!("{:02X} ", byte);
+                    let rendered = format.render(*byte);
+                    if colorize_output {
+                        print!("{} ", colorize(&rendered, *byte));
+                    } else {
+                        print!("{} ", rendered);
+                    }
                 }
                 println!();
                 return Ok(());
             }
 
-            for (i, chunk) in buf.chunks(width).enumerate() {
-                let addr = offset + (i * width) as u64;
+            let opts = DumpOpts { width, no_group, no_addr, no_ascii, format, color };
+            print!("{}", render_dump(&buf, offset, &opts));
+
+            Ok(())
+        },
+        ArgCommand::Set { offset, file, bytes, insert, delete } => {
+            let mut f = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(file)?;
 
-                if !no_addr {
-                    print!("{:08X}: ", addr);
+            if let Some(count) = delete {
+                let file_len = f.metadata()?.len();
+                if offset > file_len {
+                    return Err(format!("offset 0x{:X} is past the end of the file (0x{:X} bytes)", offset, file_len).into());
+                }
+                let count = count as u64;
+                if offset + count > file_len {
+                    return Err(format!(
+                        "cannot delete {} bytes at 0x{:X}: only 0x{:X} bytes remain in the file",
+                        count, offset, file_len - offset
+                    ).into());
                 }
 
-                let mid = width / 2;
-                for (j, byte) in chunk.iter().enumerate() {
-                    print!("{:02X} ", byte);
-                    if !no_group && j + 1 == mid {
-                        print!(" ");
-                    }
+                let write_pos = shift_backward(&mut f, offset, file_len, count)?;
+                f.set_len(write_pos)?;
+                println!("Deleted {} bytes at 0x{:X}", count, offset);
+                return Ok(());
+            }
+
+            if insert {
+                let file_len = f.metadata()?.len();
+                shift_forward(&mut f, offset, file_len, bytes.len() as u64)?;
+            }
+
+            f.seek(std::io::SeekFrom::Start(offset))?;
+            f.write_all(&bytes)?;
+            println!("Wrote {} bytes at 0x{:X}", bytes.len(), offset);
+            Ok(())
+        }
+        ArgCommand::Patch { dump, output, seek } => {
+            let mut reader = open_input(Some(dump), 0)?;
+            let mut text = String::new();
+            reader.read_to_string(&mut text)?;
+
+            let mut out = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .truncate(!seek)
+                .open(output)?;
+
+            let mut cursor = 0u64;
+            let mut written = 0usize;
+            for line in text.lines() {
+                let (addr, bytes) = parse_dump_line(line)?;
+                if bytes.is_empty() {
+                    continue;
                 }
 
-                if !no_ascii {
-                    let hex_width = if !no_addr { width * 3 + 1 } else { width * 3 };
-                    let printed_hex = chunk.len() * 3 + if chunk.len() > 8 { 1 } else { 0 };
-                    print!("{:width$}|", "", width = hex_width - printed_hex);
-
-                    for byte in chunk {
-                        let ch = if (byte.is_ascii_graphic() || *byte == b' ') && !byte.is_ascii_control() {
-                            *byte as char
-                        } else {
-                            '.'
-                        };
-                        print!("{}", ch);
+                let pos = if seek { addr.unwrap_or(cursor) } else { cursor };
+                out.seek(std::io::SeekFrom::Start(pos))?;
+                out.write_all(&bytes)?;
+                cursor = pos + bytes.len() as u64;
+                written += bytes.len();
+            }
+
+            println!("Wrote {} reconstructed bytes", written);
+            Ok(())
+        }
+        ArgCommand::Find { file, pattern, context, format, color, raw_bytes } => {
+            let mut f = File::open(&file)?;
+            let pat_len = pattern.len() as u64;
+            let opts = DumpOpts { width: 16, no_group: false, no_addr: false, no_ascii: false, format, color };
+
+            let mut probe = [0u8; 16];
+            let probe_len = f.read(&mut probe)?;
+            f.seek(std::io::SeekFrom::Start(0))?;
+            let codec = if raw_bytes { None } else { codec::Codec::sniff(&probe[..probe_len]) };
+
+            if let Some(codec) = codec {
+                let mut compressed = Vec::new();
+                f.read_to_end(&mut compressed)?;
+                let decompressed = codec.decompress(&compressed)?;
+
+                let offsets = find_matches(&mut Cursor::new(&decompressed), &pattern)?;
+                if offsets.is_empty() {
+                    println!("No matches found");
+                    return Ok(());
+                }
+
+                for addr in offsets {
+                    println!("Match at 0x{:X}", addr);
+
+                    if let Some(ctx) = context {
+                        let ctx = ctx as u64;
+                        let start = addr.saturating_sub(ctx);
+                        let end = std::cmp::min(addr + pat_len + ctx, decompressed.len() as u64);
+                        print!("{}", render_dump(&decompressed[start as usize..end as usize], start, &opts));
                     }
-                    print!("|");
                 }
 
-                println!();
+                return Ok(());
+            }
+
+            let offsets = find_matches(&mut f, &pattern)?;
+
+            if offsets.is_empty() {
+                println!("No matches found");
+                return Ok(());
+            }
+
+            let file_len = f.metadata()?.len();
+
+            for addr in offsets {
+                println!("Match at 0x{:X}", addr);
+
+                if let Some(ctx) = context {
+                    let ctx = ctx as u64;
+                    let start = addr.saturating_sub(ctx);
+                    let end = std::cmp::min(addr + pat_len + ctx, file_len);
+
+                    let mut window = vec![0u8; (end - start) as usize];
+                    f.seek(std::io::SeekFrom::Start(start))?;
+                    f.read_exact(&mut window)?;
+
+                    print!("{}", render_dump(&window, start, &opts));
+                }
             }
 
             Ok(())
-        },
-        ArgCommand::Set { offset, file, bytes } => {
-            let mut f = OpenOptions::new()
-                .write(true)
-                .open(file)?;
-            
-            f.seek(std::io::SeekFrom::Start(offset))?;
-            f.write_all(&bytes)?;
-            println!("Wrote {} bytes at 0x{:X}", bytes.len(), offset);
+        }
+        ArgCommand::Sum { file, offset, number, algo } => {
+            let mut f = open_input(file, offset)?;
+
+            let mut buf = Vec::new();
+            if let Some(len) = number {
+                buf.resize(len, 0);
+                f.read_exact(&mut buf)?;
+            } else {
+                f.read_to_end(&mut buf)?;
+            }
+
+            let digest = match algo {
+                Algo::Crc32 => format!("{:08x}", digest::crc32(&buf)),
+                Algo::Sha1 => digest::sha1(&buf).iter().map(|b| format!("{:02x}", b)).collect(),
+            };
+
+            println!("{}", digest);
             Ok(())
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_dump_aligns_ascii_panel_on_partial_rows() {
+        let buf: Vec<u8> = (0..42).collect();
+        let opts = DumpOpts { width: 16, no_group: false, no_addr: false, no_ascii: false, format: Format::Hex, color: false };
+        let out = render_dump(&buf, 0, &opts);
+        let bar_columns: Vec<usize> = out.lines().map(|line| line.find('|').expect("line should have an ascii panel")).collect();
+        assert_eq!(bar_columns.len(), 3);
+        assert!(bar_columns.windows(2).all(|w| w[0] == w[1]), "ascii panel columns should line up across rows: {:?}", bar_columns);
+    }
+
+    #[test]
+    fn render_dump_aligns_rows_shorter_than_half_the_width() {
+        let buf: Vec<u8> = (0..20).collect();
+        let opts = DumpOpts { width: 16, no_group: false, no_addr: false, no_ascii: false, format: Format::HexUpper, color: false };
+        let out = render_dump(&buf, 0, &opts);
+        let bar_columns: Vec<usize> = out.lines().map(|line| line.find('|').unwrap()).collect();
+        assert_eq!(bar_columns[0], bar_columns[1]);
+    }
+
+    #[test]
+    fn find_matches_finds_exact_pattern_across_chunk_boundary() {
+        let mut data = vec![0u8; 70_000];
+        // The first read returns 64 KiB (65_536 bytes), so this pattern straddles the boundary.
+        data[65_534..65_538].copy_from_slice(&[0xDE, 0xAD, 0xBE, 0xEF]);
+        let pattern = vec![PatternByte::Exact(0xDE), PatternByte::Exact(0xAD), PatternByte::Exact(0xBE), PatternByte::Exact(0xEF)];
+        let mut cursor = Cursor::new(data);
+        let offsets = find_matches(&mut cursor, &pattern).unwrap();
+        assert_eq!(offsets, vec![65_534]);
+    }
+
+    #[test]
+    fn find_matches_supports_wildcard_bytes() {
+        let data = vec![0x11, 0xAA, 0x33, 0x11, 0xBB, 0x33];
+        let pattern = vec![PatternByte::Exact(0x11), PatternByte::Any, PatternByte::Exact(0x33)];
+        let mut cursor = Cursor::new(data);
+        let offsets = find_matches(&mut cursor, &pattern).unwrap();
+        assert_eq!(offsets, vec![0, 3]);
+    }
+
+    #[test]
+    fn shift_forward_opens_a_gap_without_disturbing_the_tail() {
+        let mut cursor = Cursor::new(b"ABCDEF".to_vec());
+        shift_forward(&mut cursor, 2, 6, 2).unwrap();
+        let mut out = cursor.into_inner();
+        out[2..4].copy_from_slice(b"XY");
+        assert_eq!(&out, b"ABXYCDEF");
+    }
+
+    #[test]
+    fn parse_dump_line_extracts_address_and_bytes_ignoring_ascii_panel() {
+        let (addr, bytes) = parse_dump_line("00000010: AA BB  CC DD |..C.|").unwrap();
+        assert_eq!(addr, Some(0x10));
+        assert_eq!(bytes, vec![0xAA, 0xBB, 0xCC, 0xDD]);
+    }
+
+    #[test]
+    fn parse_dump_line_without_a_leading_address_has_no_addr() {
+        let (addr, bytes) = parse_dump_line("AA BB CC").unwrap();
+        assert_eq!(addr, None);
+        assert_eq!(bytes, vec![0xAA, 0xBB, 0xCC]);
+    }
+
+    #[test]
+    fn parse_dump_line_ignores_a_colon_inside_the_ascii_panel() {
+        // The `:` here belongs to the ascii text, not an address column, so it must not be
+        // mistaken for one (the text before it isn't all hex digits).
+        let (addr, bytes) = parse_dump_line("AA BB |x:y|").unwrap();
+        assert_eq!(addr, None);
+        assert_eq!(bytes, vec![0xAA, 0xBB]);
+    }
+
+    #[test]
+    fn render_array_rust_unnamed_wraps_at_width() {
+        let out = render_array(&[0xAA, 0xBB, 0xCC], ArrayFormat::Rust, None, 2);
+        assert_eq!(out, "[\n    0xAA, 0xBB,\n    0xCC,\n]\n");
+    }
+
+    #[test]
+    fn render_array_c_named_adds_declaration_and_semicolon() {
+        let out = render_array(&[0xAA, 0xBB], ArrayFormat::C, Some("FIRMWARE"), 16);
+        assert_eq!(out, "unsigned char FIRMWARE[2] = {\n    0xAA, 0xBB,\n};\n");
+    }
+
+    #[test]
+    fn discard_offset_drops_the_leading_bytes_on_an_unseekable_reader() {
+        let mut cursor = Cursor::new(b"ABCDEF".to_vec());
+        discard_offset(&mut cursor, 2).unwrap();
+        let mut rest = Vec::new();
+        cursor.read_to_end(&mut rest).unwrap();
+        assert_eq!(&rest, b"CDEF");
+    }
+
+    #[test]
+    fn discard_offset_is_a_no_op_for_zero() {
+        let mut cursor = Cursor::new(b"ABCDEF".to_vec());
+        discard_offset(&mut cursor, 0).unwrap();
+        let mut rest = Vec::new();
+        cursor.read_to_end(&mut rest).unwrap();
+        assert_eq!(&rest, b"ABCDEF");
+    }
+
+    #[test]
+    fn shift_backward_closes_a_gap_and_reports_new_length() {
+        let mut cursor = Cursor::new(b"ABXYCDEF".to_vec());
+        let new_len = shift_backward(&mut cursor, 2, 8, 2).unwrap();
+        let mut out = cursor.into_inner();
+        out.truncate(new_len as usize);
+        assert_eq!(&out, b"ABCDEF");
+    }
+}